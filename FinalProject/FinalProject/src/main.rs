@@ -1,68 +1,115 @@
-extern crate ndarray;
 extern crate csv;
 
-use ndarray::{Array1, Array2};
 use std::error::Error;
 use csv::ReaderBuilder;
 use std::fs::File;
+use std::collections::BTreeMap;
 use std::collections::HashMap;
 
-//Initialize random centroids
-fn initialize_centroids(arr: &Array2<f64>, k: usize) -> Vec<Array1<f64>> {
-    let mut centroids = Vec::new();
-    for i in 0..k {
-        // Clone row into new Array1
-        centroids.push(arr.row(i).to_owned());  
-    }
-    centroids
+//Generic k-means machinery (Clusterable trait, Lloyd's iteration, k-means++ seeding,
+//ELBG refinement, and automatic k selection) lives in kmeans_core.rs so checkin1.rs can
+//reuse it instead of maintaining its own copy.
+#[path = "../../../kmeans_core.rs"]
+mod kmeans_core;
+use kmeans_core::*;
+
+//A plain 2-D point, useful when callers only need two features. Only test_point2_clustering
+//constructs one, so it's dead code outside #[cfg(test)] builds
+#[cfg_attr(not(test), allow(dead_code))]
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Point2 {
+    x: f64,
+    y: f64,
 }
 
-//Compute euclidean distance between points
-fn distance(p1: &Array1<f64>, p2: &Array1<f64>) -> f64 {
-    p1.iter()
-        .zip(p2.iter())
-        .map(|(x1, x2)| (x1 - x2).powi(2))
-        .sum::<f64>()
-        .sqrt()
-}
+impl Clusterable for Point2 {
+    fn distance(&self, other: &Self) -> f64 {
+        ((self.x - other.x).powi(2) + (self.y - other.y).powi(2)).sqrt()
+    }
 
-//Find closest centroid
-fn find_closest_centroid(point: &Array1<f64>, centroids: &Vec<Array1<f64>>) -> usize {
-    centroids
-        .iter()
-        .enumerate()
-        .map(|(i, centroid)| (i, distance(point, centroid)))
-        .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap())
-        .map(|(i, _)| i)
-        .unwrap()
+    fn centroid<'a>(items: impl Iterator<Item = &'a Self>) -> Option<Self> {
+        let mut sum_x = 0.0;
+        let mut sum_y = 0.0;
+        let mut count = 0usize;
+        for p in items {
+            sum_x += p.x;
+            sum_y += p.y;
+            count += 1;
+        }
+        if count == 0 {
+            None
+        } else {
+            Some(Point2 {
+                x: sum_x / count as f64,
+                y: sum_y / count as f64,
+            })
+        }
+    }
 }
 
-//Recompute centroids based on current cluster
-fn recompute_centroids(arr: &Array2<f64>, labels: &Vec<usize>, k: usize) -> Vec<Array1<f64>> {
-    let mut new_centroids = vec![Array1::<f64>::zeros(arr.shape()[1]); k];
-    let mut counts = vec![0; k];
+//A scalar value paired with how many original (deduplicated) rows it represents. Social
+//data has heavy ties (e.g. like counts), so clustering one weighted point per distinct
+//value instead of one unit-weight point per row is both faster and statistically correct.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct WeightedPoint {
+    value: f64,
+    count: u32,
+}
 
-    for (i, point) in arr.axis_iter(ndarray::Axis(0)).enumerate() {
-        let cluster = labels[i];
-        new_centroids[cluster] = &new_centroids[cluster] + &point;
-        counts[cluster] += 1;
+impl Clusterable for WeightedPoint {
+    fn distance(&self, other: &Self) -> f64 {
+        (self.value - other.value).abs()
     }
 
-    for i in 0..k {
-        if counts[i] > 0 {
-            new_centroids[i] = &new_centroids[i] / counts[i] as f64;
+    //True weighted mean: sum += value * count, total += count
+    fn centroid<'a>(items: impl Iterator<Item = &'a Self>) -> Option<Self> {
+        let mut sum = 0.0;
+        let mut total = 0u32;
+        for item in items {
+            sum += item.value * item.count as f64;
+            total += item.count;
+        }
+        if total == 0 {
+            None
+        } else {
+            Some(WeightedPoint {
+                value: sum / total as f64,
+                count: total,
+            })
         }
     }
 
-    new_centroids
+    fn weight(&self) -> f64 {
+        self.count as f64
+    }
+
+    //Halfway between the two values; the default (a weighted centroid) leans toward the
+    //point with the larger count
+    fn midpoint(&self, other: &Self) -> Self {
+        WeightedPoint {
+            value: (self.value + other.value) / 2.0,
+            count: self.count + other.count,
+        }
+    }
 }
 
-//Check if centroids converged
-fn has_converged(old_centroids: &Vec<Array1<f64>>, new_centroids: &Vec<Array1<f64>>, tolerance: f64) -> bool {
-    old_centroids
-        .iter()
-        .zip(new_centroids.iter())
-        .all(|(old, new)| distance(old, new) < tolerance)
+//Fold repeated (source, likes) rows into deduplicated (likes, count) entries, so k-means
+//clusters each distinct value once instead of once per duplicate row. A BTreeMap (rather
+//than a HashMap) keeps the output ordered by value, so k-means++'s seeded Lcg always sees
+//the same input order and `seed: 42` reproduces the same clustering across runs.
+fn aggregate_counts(data: &[(String, u32)]) -> Vec<WeightedPoint> {
+    let mut counts: BTreeMap<u32, u32> = BTreeMap::new();
+    for (_, likes) in data {
+        *counts.entry(*likes).or_insert(0) += 1;
+    }
+
+    counts
+        .into_iter()
+        .map(|(value, count)| WeightedPoint {
+            value: value as f64,
+            count,
+        })
+        .collect()
 }
 
 fn main() -> Result<(), Box<dyn Error>> {
@@ -85,7 +132,7 @@ fn main() -> Result<(), Box<dyn Error>> {
         "/opt/app-root/src/FinalProject/src/reuters.csv",
     ];
 
-    let mut data: Vec<(String, u32)> = Vec::new(); 
+    let mut data: Vec<(String, u32)> = Vec::new();
     //Iterate through each CSV
     for path in file_paths {
         let file = File::open(path)?;
@@ -100,52 +147,41 @@ fn main() -> Result<(), Box<dyn Error>> {
                 //Get custom name for file
                 let news_source = file_name_map.get(path).unwrap_or(&"Unknown").to_string();
                 //Store the custom file source name and likes
-                data.push((news_source, likes)); 
+                data.push((news_source, likes));
             }
         }
     }
 
-    //Prepare the data for clustering, only using likes
-    let likes_data: Vec<f64> = data.iter().map(|(_, likes)| *likes as f64).collect();
-    let n_samples = likes_data.len();
-    let n_features = 1;
+    //Like counts are heavily tied, so cluster one weighted point per distinct value
+    //instead of one unit-weight point per row
+    let weighted_points = aggregate_counts(&data);
 
-    //Convert data into a 2D array
-    let arr = Array2::<f64>::from_shape_vec((n_samples, n_features), likes_data)?;
-
-    //Kmeans clustering
-    let k = 4;
+    //Kmeans clustering, choosing k automatically instead of hardcoding it
     let max_iters = 100;
     let tolerance = 0.0001;
 
-    //Initialize centroids starting w/random points
-    let mut centroids = initialize_centroids(&arr, k);
-    let mut labels = vec![0; n_samples];
-
-    //Perform kmeans iterations
-    for _ in 0..max_iters {
-        // Assign each point to the closest centroid
-        for (i, point) in arr.axis_iter(ndarray::Axis(0)).enumerate() {
-            let point_owned = point.to_owned();
-            labels[i] = find_closest_centroid(&point_owned, &centroids);
-        }
-
-        //Recompute centroids
-        let new_centroids = recompute_centroids(&arr, &labels, k);
-
-        //Check for convergence
-        if has_converged(&centroids, &new_centroids, tolerance) {
-            // Stop if the centroids have converged
-            break;
-        }
-
-        //Update centroids
-        centroids = new_centroids;
-    }
+    let selection = best_k(
+        &weighted_points,
+        2..=6,
+        max_iters,
+        tolerance,
+        KSelectionMethod::Silhouette,
+    )
+    .ok_or("no valid k found in the scanned range")?;
+    println!(
+        "Chose k = {} (silhouette scores by k: {:?})",
+        selection.k, selection.scores
+    );
+
+    let cluster_by_value: HashMap<u32, usize> = weighted_points
+        .iter()
+        .zip(selection.labels.iter())
+        .map(|(point, &label)| (point.value as u32, label))
+        .collect();
 
-    //Map each news to cluster and print results
-    for (i, (news_source, likes)) in data.into_iter().enumerate() {
-        let cluster = labels[i];
+    //Map each news row to its value's cluster and print results
+    for (news_source, likes) in data {
+        let cluster = cluster_by_value[&likes];
         println!("News Source: {}, Likes: {}, Cluster: {}", news_source, likes, cluster);
     }
 
@@ -155,7 +191,6 @@ fn main() -> Result<(), Box<dyn Error>> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use ndarray::arr1;
     use std::fs::File;
     use csv::ReaderBuilder;
     use std::collections::HashMap;
@@ -175,14 +210,14 @@ mod tests {
             "/opt/app-root/src/FinalProject/src/cnn.csv",
         ];
 
-        let mut data: Vec<(String, u32)> = Vec::new(); 
+        let mut data: Vec<(String, u32)> = Vec::new();
 
         //Read CSV file for CNN
         for path in file_paths {
             let file = File::open(path)?;
             let mut rdr = ReaderBuilder::new().has_headers(true).from_reader(file);
 
-            //Read each record for CSV 
+            //Read each record for CSV
             for result in rdr.records() {
                 let record = result?;
                 let likes: u32 = record.get(1).unwrap().parse()?;
@@ -190,56 +225,40 @@ mod tests {
                 if likes > 0 {
                     let news_source = file_name_map.get(path).unwrap_or(&"Unknown").to_string();
                     //Store custom file source name and likes
-                    data.push((news_source, likes)); 
+                    data.push((news_source, likes));
                 }
             }
         }
 
-        //Prepare data for clustering, only likes
-        let likes_data: Vec<f64> = data.iter().map(|(_, likes)| *likes as f64).collect();
-        let n_samples = likes_data.len();
-        let n_features = 1;
-
-        //Convert into a 2D array
-        let arr = Array2::<f64>::from_shape_vec((n_samples, n_features), likes_data)?;
+        //Prepare data for clustering: one weighted point per distinct likes value
+        let weighted_points = aggregate_counts(&data);
 
         //Perform kmeans clustering
         let k = 4;
         let max_iters = 100;
         let tolerance = 0.0001;
 
-        //Initialize centroids
-        let mut centroids = initialize_centroids(&arr, k);
-        let mut labels = vec![0; n_samples];
-
-        //Perform kmeans iterations
-        for _ in 0..max_iters {
-            //Assign each point to closest centroid
-            for (i, point) in arr.axis_iter(ndarray::Axis(0)).enumerate() {
-                let point_owned = point.to_owned();
-                labels[i] = find_closest_centroid(&point_owned, &centroids);
-            }
-
-            //Recompute centroids
-            let new_centroids = recompute_centroids(&arr, &labels, k);
-
-            //Check for convergence
-            if has_converged(&centroids, &new_centroids, tolerance) {
-                break;
-            }
-
-            //Update centroids
-            centroids = new_centroids;
-        }
+        let value_labels = kmeans(
+            &weighted_points,
+            k,
+            max_iters,
+            tolerance,
+            InitMode::KMeansPlusPlus { seed: 42 },
+        )?;
+        let cluster_by_value: HashMap<u32, usize> = weighted_points
+            .iter()
+            .zip(value_labels.iter())
+            .map(|(point, &label)| (point.value as u32, label))
+            .collect();
 
         //Verify CNN clustering
-        for (i, (news_source, likes)) in data.into_iter().enumerate() {
-            let cluster = labels[i];
+        for (news_source, likes) in data {
+            let cluster = cluster_by_value[&likes];
             assert_eq!(news_source, "CNN");
             //Check likes > 0
             assert!(likes > 0);
             // Check cluster validity
-            assert!(cluster < k); 
+            assert!(cluster < k);
         }
 
         Ok(())
@@ -260,7 +279,7 @@ mod tests {
             "/opt/app-root/src/FinalProject/src/bbc.csv",
         ];
 
-        let mut data: Vec<(String, u32)> = Vec::new(); 
+        let mut data: Vec<(String, u32)> = Vec::new();
 
         //Read BBC CSV
         for path in file_paths {
@@ -275,56 +294,149 @@ mod tests {
                 if likes > 0 {
                     let news_source = file_name_map.get(path).unwrap_or(&"Unknown").to_string();
                     //Store custom file source name and likes
-                    data.push((news_source, likes)); 
+                    data.push((news_source, likes));
                 }
             }
         }
 
-        //Prep data
-        let likes_data: Vec<f64> = data.iter().map(|(_, likes)| *likes as f64).collect();
-        let n_samples = likes_data.len();
-        let n_features = 1;
-
-        //Convert into a 2D array
-        let arr = Array2::<f64>::from_shape_vec((n_samples, n_features), likes_data)?;
+        //Prep data: one weighted point per distinct likes value
+        let weighted_points = aggregate_counts(&data);
 
         //Perform kmeans clustering
         let k = 4;
         let max_iters = 100;
         let tolerance = 0.0001;
 
-        //Initialize centroids
-        let mut centroids = initialize_centroids(&arr, k);
-        let mut labels = vec![0; n_samples];
-
-        //Perform kmeans iterations
-        for _ in 0..max_iters {
-            //Assign each point to closest centroid
-            for (i, point) in arr.axis_iter(ndarray::Axis(0)).enumerate() {
-                let point_owned = point.to_owned();
-                labels[i] = find_closest_centroid(&point_owned, &centroids);
-            }
-
-            //Recompute centroids
-            let new_centroids = recompute_centroids(&arr, &labels, k);
-
-            //Check for convergence (if centroids don't change)
-            if has_converged(&centroids, &new_centroids, tolerance) {
-                break;
-            }
-
-            //Update centroids
-            centroids = new_centroids;
-        }
+        let value_labels = kmeans(
+            &weighted_points,
+            k,
+            max_iters,
+            tolerance,
+            InitMode::KMeansPlusPlus { seed: 42 },
+        )?;
+        let cluster_by_value: HashMap<u32, usize> = weighted_points
+            .iter()
+            .zip(value_labels.iter())
+            .map(|(point, &label)| (point.value as u32, label))
+            .collect();
 
         //Verify BBC clustering
-        for (i, (news_source, likes)) in data.into_iter().enumerate() {
-            let cluster = labels[i];
+        for (news_source, likes) in data {
+            let cluster = cluster_by_value[&likes];
             assert_eq!(news_source, "BBC");
-            assert!(likes > 0); 
-            assert!(cluster < k); 
+            assert!(likes > 0);
+            assert!(cluster < k);
         }
 
         Ok(())
     }
-}
\ No newline at end of file
+
+    //k-means on a tiny synthetic set of 2-D points, exercising the generic Point2 impl
+    #[test]
+    fn test_point2_clustering() {
+        let points = vec![
+            Point2 { x: 0.0, y: 0.0 },
+            Point2 { x: 0.1, y: -0.1 },
+            Point2 { x: 10.0, y: 10.0 },
+            Point2 { x: 10.1, y: 9.9 },
+        ];
+
+        let labels = kmeans(&points, 2, 100, 0.0001, InitMode::KMeansPlusPlus { seed: 7 }).unwrap();
+
+        assert_eq!(labels[0], labels[1]);
+        assert_eq!(labels[2], labels[3]);
+        assert_ne!(labels[0], labels[2]);
+    }
+
+    //Same seed should always pick the same k-means++ centroids for the same input
+    #[test]
+    fn test_kmeans_pp_is_deterministic() {
+        let points: Vec<f64> = vec![1.0, 1.5, 2.0, 50.0, 51.0, 52.0, 100.0, 101.0];
+
+        let first = initialize_centroids_kmeans_pp(&points, 3, 99);
+        let second = initialize_centroids_kmeans_pp(&points, 3, 99);
+
+        assert_eq!(first, second);
+    }
+
+    //ELBG should never make total distortion worse than the plain k-means result it started from
+    #[test]
+    fn test_elbg_refine_does_not_increase_distortion() {
+        let points: Vec<f64> = vec![0.0, 0.1, 0.2, 5.0, 5.1, 5.2, 20.0, 20.1, 20.2];
+        // Deliberately bad seed: two centroids land in the same tight cluster
+        let centroids = vec![0.0, 0.1, 20.0];
+        let labels: Vec<usize> = points
+            .iter()
+            .map(|p| find_closest_centroid(p, &centroids))
+            .collect();
+
+        let before: f64 = cluster_distortions(&points, &labels, &centroids, 3).iter().sum();
+        let (refined_labels, refined_centroids) = elbg_refine(&points, &labels, &centroids, 3, 6);
+        let after: f64 = cluster_distortions(&points, &refined_labels, &refined_centroids, 3)
+            .iter()
+            .sum();
+
+        assert!(after <= before);
+    }
+
+    //Asking for more clusters than samples should be a real error, not a panic
+    #[test]
+    fn test_kmeans_rejects_k_greater_than_n_samples() {
+        let points: Vec<f64> = vec![1.0, 2.0, 3.0];
+
+        let result = kmeans(&points, 5, 10, 0.0001, InitMode::FirstK);
+
+        assert!(matches!(
+            result,
+            Err(KMeansError::TooManyClusters { k: 5, n_samples: 3 })
+        ));
+    }
+
+    //aggregate_counts should collapse duplicate values and the resulting centroid should
+    //match the true weighted mean, not an unweighted mean of the distinct values
+    #[test]
+    fn test_aggregate_counts_weighted_mean() {
+        let data: Vec<(String, u32)> = vec![
+            ("A".to_string(), 10),
+            ("B".to_string(), 10),
+            ("C".to_string(), 10),
+            ("D".to_string(), 20),
+        ];
+
+        let weighted_points = aggregate_counts(&data);
+        assert_eq!(weighted_points.len(), 2);
+
+        let centroid = WeightedPoint::centroid(weighted_points.iter()).unwrap();
+        // Weighted mean: (10*3 + 20*1) / 4 = 12.5, not the unweighted (10 + 20) / 2 = 15
+        assert!((centroid.value - 12.5).abs() < 1e-9);
+        assert_eq!(centroid.count, 4);
+    }
+
+    //With three well-separated synthetic clusters, silhouette-based best_k should recover k = 3
+    #[test]
+    fn test_best_k_recovers_obvious_cluster_count() {
+        let points: Vec<f64> = vec![
+            0.0, 0.1, -0.1, 0.2, 50.0, 50.1, 49.9, 50.2, 100.0, 100.1, 99.9, 100.2,
+        ];
+
+        let selection = best_k(&points, 2..=5, 100, 0.0001, KSelectionMethod::Silhouette).unwrap();
+
+        assert_eq!(selection.k, 3);
+        assert_eq!(selection.labels.len(), points.len());
+        assert_eq!(selection.scores.len(), 4);
+    }
+
+    //Same well-separated clusters, but chosen via the elbow heuristic instead of silhouette
+    #[test]
+    fn test_best_k_elbow_recovers_obvious_cluster_count() {
+        let points: Vec<f64> = vec![
+            0.0, 0.1, -0.1, 0.2, 50.0, 50.1, 49.9, 50.2, 100.0, 100.1, 99.9, 100.2,
+        ];
+
+        let selection = best_k(&points, 2..=5, 100, 0.0001, KSelectionMethod::Elbow).unwrap();
+
+        assert_eq!(selection.k, 3);
+        assert_eq!(selection.labels.len(), points.len());
+        assert_eq!(selection.scores.len(), 4);
+    }
+}
@@ -0,0 +1,579 @@
+//Generic k-means machinery shared by the main project (FinalProject/FinalProject/src/main.rs)
+//and the earlier checkin1.rs checkpoint. There's no Cargo workspace tying the two binaries
+//together, so each pulls this file in directly via `#[path] mod kmeans_core;` instead of
+//maintaining its own copy of the algorithm.
+extern crate ndarray;
+
+use ndarray::Array1;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fmt;
+
+//Errors that can occur while configuring k-means
+#[derive(Debug)]
+pub enum KMeansError {
+    TooManyClusters { k: usize, n_samples: usize },
+}
+
+impl fmt::Display for KMeansError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KMeansError::TooManyClusters { k, n_samples } => write!(
+                f,
+                "cannot form {} clusters from only {} samples",
+                k, n_samples
+            ),
+        }
+    }
+}
+
+impl std::error::Error for KMeansError {}
+
+//Total order over f64 where NaN sorts as equal, so a stray NaN never panics a comparison
+pub fn cmp_f64(a: f64, b: f64) -> Ordering {
+    a.partial_cmp(&b).unwrap_or(Ordering::Equal)
+}
+
+//A type that k-means can cluster: knows how far apart two instances are
+//and how to average a group of them into a centroid.
+pub trait Clusterable {
+    fn distance(&self, other: &Self) -> f64;
+    fn centroid<'a>(items: impl Iterator<Item = &'a Self>) -> Option<Self>
+    where
+        Self: Sized + 'a;
+
+    //The point halfway between self and other; used by ELBG to split a cluster
+    fn midpoint(&self, other: &Self) -> Self
+    where
+        Self: Sized,
+    {
+        Self::centroid([self, other].into_iter()).expect("midpoint of two points always exists")
+    }
+
+    //How many original samples this point stands for. Weighted variants override this so
+    //distortion accounting doesn't treat a collapsed duplicate as a single unit-weight point.
+    fn weight(&self) -> f64 {
+        1.0
+    }
+}
+
+//Cluster on a single scalar feature (e.g. raw likes count)
+impl Clusterable for f64 {
+    fn distance(&self, other: &Self) -> f64 {
+        (self - other).abs()
+    }
+
+    fn centroid<'a>(items: impl Iterator<Item = &'a Self>) -> Option<Self> {
+        let mut sum = 0.0;
+        let mut count = 0usize;
+        for v in items {
+            sum += v;
+            count += 1;
+        }
+        if count == 0 {
+            None
+        } else {
+            Some(sum / count as f64)
+        }
+    }
+}
+
+//Cluster on an arbitrary feature vector (e.g. likes + comments + shares)
+impl Clusterable for Array1<f64> {
+    fn distance(&self, other: &Self) -> f64 {
+        self.iter()
+            .zip(other.iter())
+            .map(|(x1, x2)| (x1 - x2).powi(2))
+            .sum::<f64>()
+            .sqrt()
+    }
+
+    fn centroid<'a>(items: impl Iterator<Item = &'a Self>) -> Option<Self> {
+        let mut items = items.peekable();
+        let n_features = items.peek()?.len();
+        let mut sum = Array1::<f64>::zeros(n_features);
+        let mut count = 0usize;
+        for v in items {
+            sum = &sum + v;
+            count += 1;
+        }
+        if count == 0 {
+            None
+        } else {
+            Some(&sum / count as f64)
+        }
+    }
+}
+
+//Tiny seedable linear congruential generator so k-means++ seeding is reproducible
+//(the crate has no RNG dependency, and tests need deterministic output)
+pub struct Lcg {
+    state: u64,
+}
+
+impl Lcg {
+    pub fn new(seed: u64) -> Self {
+        Lcg { state: seed }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        // Constants from Knuth's MMIX
+        self.state = self
+            .state
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        self.state
+    }
+
+    //Next pseudo-random value in [0, 1)
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+//How the initial centroids are chosen
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum InitMode {
+    //The original "first k rows" behavior, kept as a fallback. Neither main.rs nor
+    //checkin1.rs picks this in production, only tests exercise it, so it's dead code
+    //outside #[cfg(test)] builds
+    #[cfg_attr(not(test), allow(dead_code))]
+    FirstK,
+    //k-means++: spread the seeds out using squared-distance-weighted sampling
+    KMeansPlusPlus { seed: u64 },
+}
+
+//Initialize centroids by taking the first k points
+pub fn initialize_centroids_first_k<T: Clusterable + Clone>(points: &[T], k: usize) -> Vec<T> {
+    points.iter().take(k).cloned().collect()
+}
+
+//Initialize centroids with k-means++: pick the first centroid uniformly at random, then
+//repeatedly sample the next one with probability proportional to its squared distance
+//from the nearest centroid chosen so far.
+pub fn initialize_centroids_kmeans_pp<T: Clusterable + Clone>(points: &[T], k: usize, seed: u64) -> Vec<T> {
+    let mut rng = Lcg::new(seed);
+    let mut centroids: Vec<T> = Vec::with_capacity(k);
+
+    let first = (rng.next_f64() * points.len() as f64) as usize % points.len();
+    centroids.push(points[first].clone());
+
+    while centroids.len() < k {
+        let sq_distances: Vec<f64> = points
+            .iter()
+            .map(|point| {
+                centroids
+                    .iter()
+                    .map(|centroid| point.distance(centroid).powi(2))
+                    .fold(f64::INFINITY, f64::min)
+            })
+            .collect();
+
+        let total: f64 = sq_distances.iter().sum();
+        if total <= 0.0 {
+            // Every point coincides with an already-chosen centroid; just take the next one
+            centroids.push(points[centroids.len() % points.len()].clone());
+            continue;
+        }
+
+        let target = rng.next_f64() * total;
+        let mut cumulative = 0.0;
+        let mut chosen = points.len() - 1;
+        for (i, &sq_distance) in sq_distances.iter().enumerate() {
+            cumulative += sq_distance;
+            if cumulative >= target {
+                chosen = i;
+                break;
+            }
+        }
+        centroids.push(points[chosen].clone());
+    }
+
+    centroids
+}
+
+pub fn initialize_centroids<T: Clusterable + Clone>(points: &[T], k: usize, mode: InitMode) -> Vec<T> {
+    match mode {
+        InitMode::FirstK => initialize_centroids_first_k(points, k),
+        InitMode::KMeansPlusPlus { seed } => initialize_centroids_kmeans_pp(points, k, seed),
+    }
+}
+
+//Find the closest centroid to a point
+pub fn find_closest_centroid<T: Clusterable>(point: &T, centroids: &[T]) -> usize {
+    centroids
+        .iter()
+        .enumerate()
+        .map(|(i, centroid)| (i, point.distance(centroid)))
+        .min_by(|a, b| cmp_f64(a.1, b.1))
+        .map(|(i, _)| i)
+        .unwrap()
+}
+
+//Recompute centroids based on current cluster assignment. A cluster that lost all its
+//members is reseeded to the point farthest from its current centroid (the standard LBG fix),
+//rather than being left frozen at its stale centroid.
+pub fn recompute_centroids<T: Clusterable + Clone>(
+    points: &[T],
+    labels: &[usize],
+    k: usize,
+    old_centroids: &[T],
+) -> Vec<T> {
+    let mut new_centroids: Vec<Option<T>> = (0..k)
+        .map(|cluster| {
+            let members = points
+                .iter()
+                .zip(labels.iter())
+                .filter(|(_, &label)| label == cluster)
+                .map(|(point, _)| point);
+            T::centroid(members)
+        })
+        .collect();
+
+    let mut reseeded_indices: Vec<usize> = Vec::new();
+    for cluster in 0..k {
+        if new_centroids[cluster].is_some() {
+            continue;
+        }
+
+        let farthest = points
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| !reseeded_indices.contains(i))
+            .max_by(|(i, _), (j, _)| {
+                cmp_f64(
+                    points[*i].distance(&old_centroids[labels[*i]]),
+                    points[*j].distance(&old_centroids[labels[*j]]),
+                )
+            })
+            .map(|(i, _)| i);
+
+        new_centroids[cluster] = Some(match farthest {
+            Some(idx) => {
+                reseeded_indices.push(idx);
+                points[idx].clone()
+            }
+            None => old_centroids[cluster].clone(),
+        });
+    }
+
+    new_centroids.into_iter().map(|c| c.unwrap()).collect()
+}
+
+//Check if centroids converged
+pub fn has_converged<T: Clusterable>(old_centroids: &[T], new_centroids: &[T], tolerance: f64) -> bool {
+    old_centroids
+        .iter()
+        .zip(new_centroids.iter())
+        .all(|(old, new)| old.distance(new) < tolerance)
+}
+
+//Sum of squared distances from each cluster's members to its own centroid, each scaled by
+//the member's weight()
+pub fn cluster_distortions<T: Clusterable>(points: &[T], labels: &[usize], centroids: &[T], k: usize) -> Vec<f64> {
+    let mut distortions = vec![0.0; k];
+    for (point, &label) in points.iter().zip(labels.iter()) {
+        distortions[label] += point.distance(&centroids[label]).powi(2) * point.weight();
+    }
+    distortions
+}
+
+//Enhanced LBG refinement: plain Lloyd's iteration can converge with empty or low-utility
+//clusters. Repeatedly retire the lowest-distortion cluster and split the highest-distortion
+//one in its place, keeping the shift only if it strictly lowers total distortion.
+pub fn elbg_refine<T: Clusterable + Clone>(
+    points: &[T],
+    labels: &[usize],
+    centroids: &[T],
+    k: usize,
+    max_shifts: usize,
+) -> (Vec<usize>, Vec<T>) {
+    let mut labels = labels.to_vec();
+    let mut centroids = centroids.to_vec();
+    let mut distortions = cluster_distortions(points, &labels, &centroids, k);
+    let mut best_total: f64 = distortions.iter().sum();
+
+    for _ in 0..max_shifts {
+        let mean_distortion = best_total / k as f64;
+
+        let low = distortions
+            .iter()
+            .enumerate()
+            .filter(|&(_, &d)| d < mean_distortion)
+            .min_by(|a, b| cmp_f64(*a.1, *b.1));
+        let high = distortions
+            .iter()
+            .enumerate()
+            .filter(|&(_, &d)| d >= mean_distortion)
+            .max_by(|a, b| cmp_f64(*a.1, *b.1));
+
+        let (low_idx, high_idx) = match (low, high) {
+            (Some((l, _)), Some((h, _))) if l != h => (l, h),
+            _ => break, // nothing left to shift
+        };
+
+        let high_members: Vec<&T> = points
+            .iter()
+            .zip(labels.iter())
+            .filter(|&(_, &label)| label == high_idx)
+            .map(|(point, _)| point)
+            .collect();
+
+        if high_members.len() < 2 {
+            break; // not enough points in the donor cluster to split
+        }
+
+        // Perturb the high-distortion centroid toward its farthest member (the axis of
+        // largest spread), then toward the next-farthest from that, yielding two split points
+        let (farthest_idx, farthest) = high_members
+            .iter()
+            .enumerate()
+            .max_by(|(_, a), (_, b)| {
+                cmp_f64(
+                    a.distance(&centroids[high_idx]),
+                    b.distance(&centroids[high_idx]),
+                )
+            })
+            .unwrap();
+        let centroid_a = centroids[high_idx].midpoint(farthest);
+
+        // Exclude the point already picked as `farthest` so the two split centroids can't
+        // collapse onto the same member (and therefore onto each other)
+        let next_farthest = high_members
+            .iter()
+            .enumerate()
+            .filter(|&(i, _)| i != farthest_idx)
+            .max_by(|(_, a), (_, b)| cmp_f64(a.distance(&centroid_a), b.distance(&centroid_a)))
+            .map(|(_, p)| p)
+            .unwrap();
+        let centroid_b = centroids[high_idx].midpoint(next_farthest);
+
+        let mut candidate_centroids = centroids.clone();
+        candidate_centroids[low_idx] = centroid_a;
+        candidate_centroids[high_idx] = centroid_b;
+
+        // Local two-means: only the points that belonged to the two affected clusters move
+        let mut candidate_labels = labels.clone();
+        for (i, point) in points.iter().enumerate() {
+            if labels[i] == low_idx || labels[i] == high_idx {
+                candidate_labels[i] = find_closest_centroid(point, &candidate_centroids);
+            }
+        }
+
+        let candidate_centroids = recompute_centroids(points, &candidate_labels, k, &candidate_centroids);
+        let candidate_distortions = cluster_distortions(points, &candidate_labels, &candidate_centroids, k);
+        let candidate_total: f64 = candidate_distortions.iter().sum();
+
+        if candidate_total < best_total {
+            labels = candidate_labels;
+            centroids = candidate_centroids;
+            distortions = candidate_distortions;
+            best_total = candidate_total;
+        } else {
+            break; // shift didn't help; keep the last accepted state
+        }
+    }
+
+    (labels, centroids)
+}
+
+//Run k-means over any Clusterable type and return the cluster label for each point
+pub fn kmeans<T: Clusterable + Clone>(
+    points: &[T],
+    k: usize,
+    max_iters: usize,
+    tolerance: f64,
+    init_mode: InitMode,
+) -> Result<Vec<usize>, KMeansError> {
+    if k == 0 || k > points.len() {
+        return Err(KMeansError::TooManyClusters {
+            k,
+            n_samples: points.len(),
+        });
+    }
+
+    let mut centroids = initialize_centroids(points, k, init_mode);
+    let mut labels = vec![0; points.len()];
+
+    for _ in 0..max_iters {
+        // Assign each point to the closest centroid
+        for (i, point) in points.iter().enumerate() {
+            labels[i] = find_closest_centroid(point, &centroids);
+        }
+
+        // Recompute centroids
+        let new_centroids = recompute_centroids(points, &labels, k, &centroids);
+
+        // Check for convergence
+        let converged = has_converged(&centroids, &new_centroids, tolerance);
+        centroids = new_centroids;
+        if converged {
+            break;
+        }
+    }
+
+    // Try to escape a local minimum with an ELBG pass, but only keep it if it actually helps
+    let plain_distortion: f64 = cluster_distortions(points, &labels, &centroids, k).iter().sum();
+    let (refined_labels, refined_centroids) = elbg_refine(points, &labels, &centroids, k, k * 2);
+    let refined_distortion: f64 = cluster_distortions(points, &refined_labels, &refined_centroids, k).iter().sum();
+
+    Ok(if refined_distortion < plain_distortion {
+        refined_labels
+    } else {
+        labels
+    })
+}
+
+//How to automatically pick k out of a scanned range
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum KSelectionMethod {
+    //Pick the k at the point of maximum curvature in the distortion-vs-k curve. checkin1.rs's
+    //production main() picks this, but main.rs's never does (only its tests do), so this
+    //variant would be dead code in main.rs's non-test build without the allow below
+    #[cfg_attr(not(test), allow(dead_code))]
+    Elbow,
+    //Pick the k with the highest mean silhouette coefficient
+    Silhouette,
+}
+
+//The result of scanning a range of k values
+pub struct BestK {
+    pub k: usize,
+    pub labels: Vec<usize>,
+    //(k, score) for every candidate in the scanned range, in order; elbow scores are total
+    //distortion, silhouette scores are the mean silhouette coefficient
+    pub scores: Vec<(usize, f64)>,
+}
+
+//Mean silhouette coefficient: for each point, s = (b - a) / max(a, b), where a is the mean
+//distance to its own cluster and b is the mean distance to the nearest other cluster.
+pub fn mean_silhouette<T: Clusterable>(points: &[T], labels: &[usize], k: usize) -> f64 {
+    if k < 2 {
+        return 0.0;
+    }
+
+    let mut total = 0.0;
+    let mut counted = 0usize;
+
+    for (point, &own_cluster) in points.iter().zip(labels.iter()) {
+        let own_count = labels.iter().filter(|&&l| l == own_cluster).count();
+        if own_count <= 1 {
+            continue; // silhouette is undefined for a singleton cluster
+        }
+
+        let a = points
+            .iter()
+            .zip(labels.iter())
+            .filter(|&(_, &l)| l == own_cluster)
+            .map(|(other, _)| point.distance(other))
+            .sum::<f64>()
+            / (own_count - 1) as f64;
+
+        let b = (0..k)
+            .filter(|&cluster| cluster != own_cluster)
+            .filter_map(|cluster| {
+                let members: Vec<&T> = points
+                    .iter()
+                    .zip(labels.iter())
+                    .filter(|&(_, &l)| l == cluster)
+                    .map(|(p, _)| p)
+                    .collect();
+                if members.is_empty() {
+                    None
+                } else {
+                    Some(
+                        members.iter().map(|other| point.distance(other)).sum::<f64>()
+                            / members.len() as f64,
+                    )
+                }
+            })
+            .fold(f64::INFINITY, f64::min);
+
+        if b.is_finite() {
+            total += (b - a) / a.max(b);
+            counted += 1;
+        }
+    }
+
+    if counted == 0 {
+        0.0
+    } else {
+        total / counted as f64
+    }
+}
+
+//Elbow heuristic: the k at the point of maximum curvature (second derivative) of the
+//normalized distortion-vs-k curve
+pub fn elbow_k(distortions: &[(usize, f64)]) -> usize {
+    if distortions.len() < 3 {
+        return distortions.first().map(|&(k, _)| k).unwrap_or(1);
+    }
+
+    let max_distortion = distortions.iter().map(|&(_, d)| d).fold(0.0, f64::max);
+    let normalized: Vec<f64> = distortions
+        .iter()
+        .map(|&(_, d)| if max_distortion > 0.0 { d / max_distortion } else { 0.0 })
+        .collect();
+
+    let mut best_idx = 1;
+    let mut best_curvature = f64::MIN;
+    for i in 1..normalized.len() - 1 {
+        let curvature = normalized[i - 1] - 2.0 * normalized[i] + normalized[i + 1];
+        if curvature > best_curvature {
+            best_curvature = curvature;
+            best_idx = i;
+        }
+    }
+
+    distortions[best_idx].0
+}
+
+//Scan k_range, run k-means for each candidate k, and automatically choose one via either the
+//elbow heuristic or mean silhouette, returning the chosen k, its labels, and the score curve
+//so callers can report why that k was used.
+pub fn best_k<T: Clusterable + Clone>(
+    points: &[T],
+    k_range: impl Iterator<Item = usize>,
+    max_iters: usize,
+    tolerance: f64,
+    method: KSelectionMethod,
+) -> Option<BestK> {
+    let mut distortions: Vec<(usize, f64)> = Vec::new();
+    let mut silhouettes: Vec<(usize, f64)> = Vec::new();
+    let mut labels_by_k: HashMap<usize, Vec<usize>> = HashMap::new();
+
+    for k in k_range {
+        let labels = kmeans(points, k, max_iters, tolerance, InitMode::KMeansPlusPlus { seed: 42 }).ok()?;
+        let seed_centroids = initialize_centroids_first_k(points, k);
+        let centroids = recompute_centroids(points, &labels, k, &seed_centroids);
+        let distortion: f64 = cluster_distortions(points, &labels, &centroids, k).iter().sum();
+        distortions.push((k, distortion));
+
+        // mean_silhouette is O(n^2) (all pairwise distances); skip it for callers that picked
+        // the elbow method and will never look at the silhouette scores
+        if method == KSelectionMethod::Silhouette {
+            silhouettes.push((k, mean_silhouette(points, &labels, k)));
+        }
+
+        labels_by_k.insert(k, labels);
+    }
+
+    if distortions.is_empty() {
+        return None;
+    }
+
+    let (chosen_k, scores) = match method {
+        KSelectionMethod::Elbow => (elbow_k(&distortions), distortions),
+        KSelectionMethod::Silhouette => {
+            let chosen = silhouettes
+                .iter()
+                .max_by(|a, b| cmp_f64(a.1, b.1))
+                .map(|&(k, _)| k)
+                .unwrap();
+            (chosen, silhouettes)
+        }
+    };
+
+    let labels = labels_by_k.remove(&chosen_k)?;
+    Some(BestK { k: chosen_k, labels, scores })
+}